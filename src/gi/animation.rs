@@ -0,0 +1,232 @@
+use bevy::prelude::*;
+
+use crate::gi::types::{OmniLightSource2D, SkylightLight2D, SpotLight2D};
+
+/// Per-segment easing applied between two keyframes, in the spirit of WebRender's
+/// property-binding interpolation.
+#[derive(Copy, Clone, Debug)]
+pub enum EasingCurve {
+    Linear,
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+}
+
+impl EasingCurve {
+    fn apply(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            EasingCurve::Linear => t,
+            EasingCurve::EaseIn => t * t,
+            EasingCurve::EaseOut => 1.0 - (1.0 - t) * (1.0 - t),
+            EasingCurve::EaseInOut => t * t * (3.0 - 2.0 * t),
+        }
+    }
+}
+
+/// How the track cursor behaves once it reaches the end of the keyframe range.
+#[derive(Copy, Clone, Debug, Default)]
+pub enum PlaybackMode {
+    #[default]
+    Loop,
+    PingPong,
+    Once,
+}
+
+/// A single keyframe: a value to reach by `time` seconds, and the easing used for the segment
+/// leading into it.
+#[derive(Copy, Clone, Debug)]
+pub struct Keyframe<T> {
+    pub time: f32,
+    pub value: T,
+    pub ease: EasingCurve,
+}
+
+/// A keyframe track for one animated property. Empty and single-keyframe tracks are valid and
+/// just hold their value steady.
+#[derive(Clone, Debug)]
+pub struct Track<T> {
+    pub keyframes: Vec<Keyframe<T>>,
+}
+
+impl<T> Default for Track<T> {
+    fn default() -> Self {
+        Self {
+            keyframes: Vec::new(),
+        }
+    }
+}
+
+impl<T: Copy + Lerp> Track<T> {
+    fn duration(&self) -> f32 {
+        self.keyframes.last().map_or(0.0, |k| k.time)
+    }
+
+    /// Samples the track at `cursor` seconds, assuming keyframes are sorted by `time`.
+    fn sample(&self, cursor: f32) -> Option<T> {
+        match self.keyframes.as_slice() {
+            [] => None,
+            [only] => Some(only.value),
+            keyframes => {
+                let end = keyframes
+                    .iter()
+                    .position(|k| k.time >= cursor)
+                    .unwrap_or(keyframes.len() - 1)
+                    .max(1);
+                let start = end - 1;
+                let (k0, k1) = (&keyframes[start], &keyframes[end]);
+                let span = (k1.time - k0.time).max(f32::EPSILON);
+                let t = k1.ease.apply((cursor - k0.time) / span);
+                Some(k0.value.lerp(k1.value, t))
+            }
+        }
+    }
+}
+
+/// Linear interpolation for the property types a [`Track`] can animate.
+pub trait Lerp {
+    fn lerp(self, other: Self, t: f32) -> Self;
+}
+
+impl Lerp for f32 {
+    fn lerp(self, other: Self, t: f32) -> Self {
+        self + (other - self) * t
+    }
+}
+
+impl Lerp for Vec3 {
+    fn lerp(self, other: Self, t: f32) -> Self {
+        Vec3::lerp(self, other, t)
+    }
+}
+
+impl Lerp for Color {
+    fn lerp(self, other: Self, t: f32) -> Self {
+        Color::rgba(
+            self.r().lerp(other.r(), t),
+            self.g().lerp(other.g(), t),
+            self.b().lerp(other.b(), t),
+            self.a().lerp(other.a(), t),
+        )
+    }
+}
+
+/// Drives keyframed animation of a light's base properties, which are written back into the
+/// light component unchanged each frame so that jitter (`jitter_intensity`/`jitter_translation`)
+/// keeps layering on top of the animated value rather than fighting it.
+#[derive(Component, Clone, Debug, Default)]
+pub struct AnimatedLight2D {
+    pub intensity: Track<f32>,
+    pub color: Track<Color>,
+    pub falloff: Track<Vec3>,
+
+    /// Animates `SpotLight2D::outer_angle`; ignored on entities without a `SpotLight2D`.
+    pub cone_angle: Track<f32>,
+
+    pub playback: PlaybackMode,
+    pub cursor: f32,
+
+    /// `-1.0` or `1.0`; flipped at each end by [`PlaybackMode::PingPong`].
+    pub direction: f32,
+}
+
+impl AnimatedLight2D {
+    fn longest_track_duration(&self) -> f32 {
+        [
+            self.intensity.duration(),
+            self.color.duration(),
+            self.falloff.duration(),
+            self.cone_angle.duration(),
+        ]
+        .into_iter()
+        .fold(0.0, f32::max)
+    }
+
+    fn advance(&mut self, delta_seconds: f32) {
+        let duration = self.longest_track_duration();
+        if duration <= 0.0 {
+            return;
+        }
+
+        let direction = if self.direction == 0.0 {
+            1.0
+        } else {
+            self.direction
+        };
+        self.cursor += delta_seconds * direction;
+
+        match self.playback {
+            PlaybackMode::Loop => {
+                self.cursor = self.cursor.rem_euclid(duration);
+            }
+            PlaybackMode::PingPong => {
+                if self.cursor > duration {
+                    self.cursor = duration - (self.cursor - duration);
+                    self.direction = -1.0;
+                } else if self.cursor < 0.0 {
+                    self.cursor = -self.cursor;
+                    self.direction = 1.0;
+                }
+            }
+            PlaybackMode::Once => {
+                self.cursor = self.cursor.clamp(0.0, duration);
+            }
+        }
+    }
+}
+
+/// Advances every [`AnimatedLight2D`] cursor and writes the interpolated values back into the
+/// light components it's attached to. Must run before
+/// [`crate::gi::pipeline_assets::system_extract_pipeline_assets`] so the extract step sees this
+/// frame's animated values, not last frame's.
+pub fn system_advance_light_animations(
+    time: Res<Time>,
+    mut query: Query<(
+        &mut AnimatedLight2D,
+        Option<&mut OmniLightSource2D>,
+        Option<&mut SkylightLight2D>,
+        Option<&mut SpotLight2D>,
+    )>,
+) {
+    let delta_seconds = time.delta_seconds();
+    for (mut animation, omni_light, skylight, spot_light) in query.iter_mut() {
+        animation.advance(delta_seconds);
+        let cursor = animation.cursor;
+
+        if let Some(mut omni_light) = omni_light {
+            if let Some(intensity) = animation.intensity.sample(cursor) {
+                omni_light.intensity = intensity;
+            }
+            if let Some(color) = animation.color.sample(cursor) {
+                omni_light.color = color;
+            }
+            if let Some(falloff) = animation.falloff.sample(cursor) {
+                omni_light.falloff = falloff;
+            }
+        }
+
+        if let Some(mut skylight) = skylight {
+            if let Some(intensity) = animation.intensity.sample(cursor) {
+                skylight.intensity = intensity;
+            }
+            if let Some(color) = animation.color.sample(cursor) {
+                skylight.color = color;
+            }
+        }
+
+        if let Some(mut spot_light) = spot_light {
+            if let Some(intensity) = animation.intensity.sample(cursor) {
+                spot_light.intensity = intensity;
+            }
+            if let Some(color) = animation.color.sample(cursor) {
+                spot_light.color = color;
+            }
+            if let Some(falloff) = animation.falloff.sample(cursor) {
+                spot_light.falloff = falloff;
+            }
+            if let Some(outer_angle) = animation.cone_angle.sample(cursor) {
+                spot_light.outer_angle = outer_angle;
+            }
+        }
+    }
+}
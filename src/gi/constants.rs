@@ -0,0 +1,14 @@
+pub const GI_SCREEN_PROBE_SIZE: i32 = 8;
+
+/// Default soft cap on how many of each GI entity kind get uploaded to the GPU per frame.
+/// Storage buffers backing these arrays grow on demand, so this is a sanity ceiling rather than
+/// a hard allocation limit: see [`crate::LightPassParams::max_gi_entities`].
+pub const DEFAULT_MAX_GI_ENTITIES: usize = 512;
+
+/// WGSL source for the direct-light pass: PCSS soft shadows for [`crate::OmniLightSource2D`]
+/// (Poisson-disc sampling, per-pixel rotation, blocker-search penumbra) and cone attenuation for
+/// [`crate::SpotLight2D`], both feeding the same accumulation. Struct layouts in the shader must
+/// stay in lockstep with `src/gi/types_gpu.rs`. Not yet bound by a compute pipeline — this crate
+/// doesn't have a render-graph node in this tree to bind it into.
+#[allow(dead_code)]
+pub(crate) const DIRECT_LIGHT_SHADER: &str = include_str!("../../assets/shaders/direct_light.wgsl");
@@ -0,0 +1,6 @@
+pub mod animation;
+pub mod constants;
+pub(crate) mod pipeline_assets;
+pub mod resource;
+pub mod types;
+pub mod types_gpu;
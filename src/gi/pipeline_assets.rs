@@ -1,36 +1,45 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
 use bevy::prelude::*;
-use bevy::render::render_resource::UniformBuffer;
+use bevy::render::render_resource::{StorageBuffer, UniformBuffer};
 use bevy::render::renderer::{RenderDevice, RenderQueue};
 use bevy::render::Extract;
+use bevy::utils::HashMap;
 use rand::{thread_rng, Rng};
 
 use super::types_gpu::{
-    GpuLightOccluder2DArray, GpuOmniLightSourceArray, GpuProbeArray, GpuSkylightMaskArray,
+    GpuLightOccluder2DArray, GpuOmniLightSourceArray, GpuProbeArray, GpuProbeData,
+    GpuSkylightMaskArray, GpuSpotLightSourceArray,
 };
 use crate::gi::constants::GI_SCREEN_PROBE_SIZE;
-use crate::gi::resource::ComputedTargetSizes;
-use crate::gi::types::{LightOccluder2D, OmniLightSource2D, SkylightLight2D, SkylightMask2D};
+use crate::gi::resource::{ComputedTargetSizes, GiDispatchState};
+use crate::gi::types::{
+    LightOccluder2D, OmniLightSource2D, SkylightLight2D, SkylightMask2D, SpotLight2D,
+};
 use crate::gi::types_gpu::{
-    GpuCameraParams, GpuLightOccluder2D, GpuLightPassParams, GpuOmniLightSource,
-    GpuSkylightMaskData,
+    GpuCameraParams, GpuCameraParamsArray, GpuLightOccluder2D, GpuLightPassParams,
+    GpuOmniLightSource, GpuSkylightMaskData, GpuSpotLightSource,
 };
 use crate::prelude::BevyMagicLight2DSettings;
-use crate::FloorCamera;
+use crate::{FloorCamera, GiComputeMode};
 
 #[rustfmt::skip]
 #[derive(Default, Resource)]
 pub(crate) struct LightPassPipelineAssets {
-    pub camera_params:     UniformBuffer<GpuCameraParams>,
-    pub light_pass_params: UniformBuffer<GpuLightPassParams>,
-    pub probes:            UniformBuffer<GpuProbeArray>,
-    pub light_sources:     UniformBuffer<GpuOmniLightSourceArray>,
-    pub light_occluders:   UniformBuffer<GpuLightOccluder2DArray>,
-    pub skylight_masks:    UniformBuffer<GpuSkylightMaskArray>,
+    pub camera_params:      StorageBuffer<GpuCameraParamsArray>,
+    pub light_pass_params:  UniformBuffer<GpuLightPassParams>,
+    pub probes:             StorageBuffer<GpuProbeArray>,
+    pub light_sources:      StorageBuffer<GpuOmniLightSourceArray>,
+    pub spot_light_sources: StorageBuffer<GpuSpotLightSourceArray>,
+    pub light_occluders:    StorageBuffer<GpuLightOccluder2DArray>,
+    pub skylight_masks:     StorageBuffer<GpuSkylightMaskArray>,
 }
 
 impl LightPassPipelineAssets {
     pub fn write_buffer(&mut self, device: &RenderDevice, queue: &RenderQueue) {
         self.light_sources.write_buffer(device, queue);
+        self.spot_light_sources.write_buffer(device, queue);
         self.light_occluders.write_buffer(device, queue);
         self.camera_params.write_buffer(device, queue);
         self.light_pass_params.write_buffer(device, queue);
@@ -50,113 +59,206 @@ pub(crate) fn system_prepare_pipeline_assets(
 
 #[rustfmt::skip]
 pub(crate) fn system_extract_pipeline_assets(
-    res_light_settings:         Extract<Res<BevyMagicLight2DSettings>>,
-    res_target_sizes:           Extract<Res<ComputedTargetSizes>>,
-
-    query_lights:               Extract<Query<(&Transform, &OmniLightSource2D, &ComputedVisibility)>>,
-    query_occluders:            Extract<Query<(&LightOccluder2D, &Transform, &ComputedVisibility)>>,
-    query_camera:               Extract<Query<(&Camera, &GlobalTransform), With<FloorCamera>>>,
-    query_masks:                Extract<Query<(&Transform, &SkylightMask2D)>>,
-    query_skylight_light:       Extract<Query<&SkylightLight2D>>,
-
-    mut gpu_target_sizes:       ResMut<ComputedTargetSizes>,
-    mut gpu_pipeline_assets:    ResMut<LightPassPipelineAssets>,
-    mut gpu_frame_counter:      Local<i32>,
+    res_light_settings:              Extract<Res<BevyMagicLight2DSettings>>,
+    res_target_sizes:                Extract<Res<ComputedTargetSizes>>,
+
+    query_lights:                    Extract<Query<(&Transform, &OmniLightSource2D, &ComputedVisibility)>>,
+    query_spot_lights:               Extract<Query<(&Transform, &SpotLight2D, &ComputedVisibility)>>,
+    query_occluders:                 Extract<Query<(&LightOccluder2D, &Transform, &ComputedVisibility)>>,
+    query_camera:                    Extract<Query<(Entity, &Camera, &GlobalTransform), With<FloorCamera>>>,
+    query_masks:                     Extract<Query<(&Transform, &SkylightMask2D)>>,
+    query_skylight_light:            Extract<Query<&SkylightLight2D>>,
+
+    mut gpu_target_sizes:            ResMut<ComputedTargetSizes>,
+    mut gpu_pipeline_assets:         ResMut<LightPassPipelineAssets>,
+    mut gpu_dispatch_state:          ResMut<GiDispatchState>,
+    mut gpu_frame_counter:           Local<i32>,
+    mut gi_scene_dirty_state:        Local<(u64, u32)>,
+
+    // Maps each `FloorCamera` entity to a stable probe/camera-params slot, kept across frames so
+    // Bevy's query iteration order (not guaranteed stable across despawn/respawn) never silently
+    // hands one camera's temporal-reprojection history to another.
+    mut camera_slot_map:             Local<HashMap<Entity, usize>>,
+
+    // Soft-cap overflow is logged once per distinct count instead of every frame, so a scene
+    // that's been sitting over `max_gi_entities` for a while doesn't spam the log at frame rate.
+    mut light_sources_warned_count:      Local<usize>,
+    mut spot_light_sources_warned_count: Local<usize>,
+    mut light_occluders_warned_count:    Local<usize>,
+    mut skylight_masks_warned_count:     Local<usize>,
 ) {
     let light_pass_config = &res_light_settings.light_pass_params;
 
     *gpu_target_sizes = **res_target_sizes;
 
+    let max_gi_entities = light_pass_config.max_gi_entities;
+
     {
         let light_sources = gpu_pipeline_assets.light_sources.get_mut();
-        light_sources.count = 0;
         let mut rng = thread_rng();
-        for (transform, light_source, visibility) in query_lights.iter() {
-            if visibility.is_visible() {
-                if light_sources.count as usize >= light_sources.data.len() {
-                    break;
+        extract_capped(
+            &mut light_sources.data,
+            max_gi_entities,
+            &mut *light_sources_warned_count,
+            "omni lights",
+            |data| {
+                for (transform, light_source, visibility) in query_lights.iter() {
+                    if visibility.is_visible() {
+                        // `radius`/`shadow_softness` ride along via `..*light_source` so the
+                        // direct-light PCSS sampling in the WGSL shader can treat this light as
+                        // an area source.
+                        data.push(GpuOmniLightSource::new(
+                            OmniLightSource2D {
+                                intensity: light_source.intensity
+                                    + rng.gen_range(-1.0..1.0) * light_source.jitter_intensity,
+                                ..*light_source
+                            },
+                            Vec2::new(
+                                transform.translation.x
+                                    + rng.gen_range(-1.0..1.0) * light_source.jitter_translation,
+                                transform.translation.y
+                                    + rng.gen_range(-1.0..1.0) * light_source.jitter_translation,
+                            ),
+                        ));
+                    }
                 }
-                light_sources.data[light_sources.count as usize] = GpuOmniLightSource::new(
-                    OmniLightSource2D {
-                        intensity: light_source.intensity
-                            + rng.gen_range(-1.0..1.0) * light_source.jitter_intensity,
-                        ..*light_source
-                    },
-                    Vec2::new(
-                        transform.translation.x
-                            + rng.gen_range(-1.0..1.0) * light_source.jitter_translation,
-                        transform.translation.y
-                            + rng.gen_range(-1.0..1.0) * light_source.jitter_translation,
-                    ),
-                );
-                light_sources.count += 1;
-            }
-        }
+            },
+        );
+        light_sources.count = light_sources.data.len() as i32;
+    }
+
+    {
+        let spot_light_sources = gpu_pipeline_assets.spot_light_sources.get_mut();
+        let mut rng = thread_rng();
+        extract_capped(
+            &mut spot_light_sources.data,
+            max_gi_entities,
+            &mut *spot_light_sources_warned_count,
+            "spot lights",
+            |data| {
+                for (transform, spot_light, visibility) in query_spot_lights.iter() {
+                    if visibility.is_visible() {
+                        data.push(GpuSpotLightSource::new(
+                            SpotLight2D {
+                                intensity: spot_light.intensity
+                                    + rng.gen_range(-1.0..1.0) * spot_light.jitter_intensity,
+                                ..*spot_light
+                            },
+                            Vec2::new(
+                                transform.translation.x
+                                    + rng.gen_range(-1.0..1.0) * spot_light.jitter_translation,
+                                transform.translation.y
+                                    + rng.gen_range(-1.0..1.0) * spot_light.jitter_translation,
+                            ),
+                        ));
+                    }
+                }
+            },
+        );
+        spot_light_sources.count = spot_light_sources.data.len() as i32;
     }
 
     {
         let light_occluders = gpu_pipeline_assets.light_occluders.get_mut();
-        light_occluders.count = 0;
-        for (occluder, transform, visibility) in query_occluders.iter() {
-            if visibility.is_visible() {
-                if light_occluders.count as usize >= light_occluders.data.len() {
-                    break;
+        extract_capped(
+            &mut light_occluders.data,
+            max_gi_entities,
+            &mut *light_occluders_warned_count,
+            "light occluders",
+            |data| {
+                for (occluder, transform, visibility) in query_occluders.iter() {
+                    if visibility.is_visible() {
+                        data.push(GpuLightOccluder2D::new(transform, occluder.h_size));
+                    }
                 }
-                light_occluders.data[light_occluders.count as usize] = GpuLightOccluder2D::new(
-                    transform,
-                    occluder.h_size,
-                );
-                light_occluders.count += 1;
-            }
-        }
+            },
+        );
+        light_occluders.count = light_occluders.data.len() as i32;
     }
 
-    {   
+    {
         let skylight_masks = gpu_pipeline_assets.skylight_masks.get_mut();
-        skylight_masks.count = 0;
-        for (transform, mask) in query_masks.iter() {
-            if skylight_masks.count as usize >= skylight_masks.data.len() {
-                break;
-            }
-            skylight_masks.data[skylight_masks.count as usize] = GpuSkylightMaskData::new(
-                transform.translation.truncate(),
-                mask.h_size,
-            );
-            skylight_masks.count += 1;
-        }
+        extract_capped(
+            &mut skylight_masks.data,
+            max_gi_entities,
+            &mut *skylight_masks_warned_count,
+            "skylight masks",
+            |data| {
+                for (transform, mask) in query_masks.iter() {
+                    data.push(GpuSkylightMaskData::new(transform.translation.truncate(), mask.h_size));
+                }
+            },
+        );
+        skylight_masks.count = skylight_masks.data.len() as i32;
     }
 
+    // Each `FloorCamera` gets its own `GpuCameraParams` slot and its own range of probe slots
+    // (`probe_frame_size` wide) within the shared probe storage buffer, so split-screen and
+    // picture-in-picture viewports each accumulate independently-correct screen-space probes.
+    // `FloorCamera` (rather than `SpriteCamera`) is the right marker here: `SpriteCamera` is also
+    // worn by the walls/objects cameras that composite the very same viewport, and giving each of
+    // those its own GI slot would triple the work for what is logically one camera.
+    let probe_frame_size = (GI_SCREEN_PROBE_SIZE * GI_SCREEN_PROBE_SIZE) as usize;
     {
-        if let Ok((camera, camera_global_transform)) = query_camera.get_single() {
-            let mut camera_params = gpu_pipeline_assets.camera_params.get_mut();
+        let camera_params = gpu_pipeline_assets.camera_params.get_mut();
+        camera_params.data.clear();
+
+        if query_camera.is_empty() {
+            log::warn!("Failed to get any FloorCamera for the GI light pass");
+        }
+
+        // Keep each live camera's existing slot and drop entries for cameras that despawned, so a
+        // camera's slot index never changes while it's alive regardless of query iteration order.
+        camera_slot_map.retain(|entity, _| query_camera.contains(*entity));
+        let mut used_slots: std::collections::HashSet<usize> =
+            camera_slot_map.values().copied().collect();
+        for (entity, ..) in query_camera.iter() {
+            camera_slot_map.entry(entity).or_insert_with(|| {
+                let slot = (0..).find(|slot| !used_slots.contains(slot)).unwrap();
+                used_slots.insert(slot);
+                slot
+            });
+        }
+
+        // The probe atlas carries temporal-reprojection history in every slot but the one this
+        // frame writes to, so it only ever grows to fit the highest assigned slot and is never
+        // cleared or shrunk.
+        let probes = gpu_pipeline_assets.probes.get_mut();
+        let slot_count = camera_slot_map.values().copied().max().map_or(0, |max| max + 1);
+        let needed_probe_len = slot_count * probe_frame_size;
+        if probes.data.len() < needed_probe_len {
+            probes.data.resize(needed_probe_len, GpuProbeData::default());
+        }
+        camera_params.data.resize(slot_count, GpuCameraParams::default());
+
+        for (entity, camera, camera_global_transform) in query_camera.iter() {
+            let camera_index = camera_slot_map[&entity];
             let projection = camera.projection_matrix();
             let inverse_projection = projection.inverse();
             let view = camera_global_transform.compute_matrix();
             let inverse_view = view.inverse();
 
-            camera_params.view_proj = projection * inverse_view;
-            camera_params.inverse_view_proj = view * inverse_projection;
-            camera_params.screen_size = Vec2::new(
-                gpu_target_sizes.primary_target_size.x,
-                gpu_target_sizes.primary_target_size.y,
-            );
-            camera_params.screen_size_inv = Vec2::new(
-                1.0 / gpu_target_sizes.primary_target_size.x,
-                1.0 / gpu_target_sizes.primary_target_size.y,
-            );
+            // Each camera's own render target may differ in size from the primary one (e.g. a
+            // split-screen or minimap viewport), so fall back to the global target size only when
+            // this camera doesn't have a resolved viewport yet.
+            let screen_size = camera
+                .logical_viewport_size()
+                .unwrap_or(gpu_target_sizes.primary_target_size);
 
             let scale = 2.0;
-            camera_params.sdf_scale     = Vec2::splat(scale);
-            camera_params.inv_sdf_scale = Vec2::splat(1. / scale);
+            camera_params.data[camera_index] = GpuCameraParams {
+                view_proj: projection * inverse_view,
+                inverse_view_proj: view * inverse_projection,
+                screen_size,
+                screen_size_inv: Vec2::new(1.0 / screen_size.x, 1.0 / screen_size.y),
+                sdf_scale: Vec2::splat(scale),
+                inv_sdf_scale: Vec2::splat(1. / scale),
+            };
 
-            let probes = gpu_pipeline_assets.probes.get_mut();
-            probes.data[*gpu_frame_counter as usize].camera_pose =
-                camera_global_transform.translation().truncate();
-        } else {
-            log::warn!("Failed to get camera");
-            let probes = gpu_pipeline_assets.probes.get_mut();
-            probes.data[*gpu_frame_counter as usize].camera_pose = Vec2::ZERO;
+            probes.data[camera_index * probe_frame_size + *gpu_frame_counter as usize]
+                .camera_pose = camera_global_transform.translation().truncate();
         }
+        camera_params.count = camera_params.data.len() as i32;
     }
 
     {
@@ -175,6 +277,8 @@ pub(crate) fn system_extract_pipeline_assets(
         light_pass_params.indirect_light_contrib      = light_pass_config.indirect_light_contrib;
         light_pass_params.indirect_rays_radius_factor = light_pass_config.indirect_rays_radius_factor;
         light_pass_params.indirect_rays_per_sample    = light_pass_config.indirect_rays_per_sample;
+        light_pass_params.shadow_samples              = light_pass_config.shadow_samples;
+        light_pass_params.shadow_softness_multiplier  = light_pass_config.shadow_softness_multiplier;
     }
 
     {
@@ -187,5 +291,118 @@ pub(crate) fn system_extract_pipeline_assets(
         }
     }
 
+    {
+        // Hash only the state that actually affects output, and only the *un-jittered* values:
+        // per-frame jitter changes the hash every frame by design, which would defeat detecting
+        // a static scene.
+        let mut scene_hasher = DefaultHasher::new();
+        for (transform, light_source, visibility) in query_lights.iter() {
+            if visibility.is_visible() {
+                hash_vec2(&mut scene_hasher, transform.translation.truncate());
+                hash_f32(&mut scene_hasher, light_source.intensity);
+                hash_color(&mut scene_hasher, light_source.color);
+                hash_vec3(&mut scene_hasher, light_source.falloff);
+                hash_f32(&mut scene_hasher, light_source.radius);
+                hash_f32(&mut scene_hasher, light_source.shadow_softness);
+            }
+        }
+        for (transform, spot_light, visibility) in query_spot_lights.iter() {
+            if visibility.is_visible() {
+                hash_vec2(&mut scene_hasher, transform.translation.truncate());
+                hash_f32(&mut scene_hasher, spot_light.intensity);
+                hash_color(&mut scene_hasher, spot_light.color);
+                hash_vec3(&mut scene_hasher, spot_light.falloff);
+                hash_f32(&mut scene_hasher, spot_light.direction);
+                hash_f32(&mut scene_hasher, spot_light.outer_angle);
+                hash_f32(&mut scene_hasher, spot_light.inner_angle);
+            }
+        }
+        for (occluder, transform, visibility) in query_occluders.iter() {
+            if visibility.is_visible() {
+                hash_vec2(&mut scene_hasher, transform.translation.truncate());
+                hash_vec2(&mut scene_hasher, occluder.h_size);
+            }
+        }
+        for (transform, mask) in query_masks.iter() {
+            hash_vec2(&mut scene_hasher, transform.translation.truncate());
+            hash_vec2(&mut scene_hasher, mask.h_size);
+        }
+        for skylight in query_skylight_light.iter() {
+            hash_color(&mut scene_hasher, skylight.color);
+            hash_f32(&mut scene_hasher, skylight.intensity);
+        }
+        for (_, _, camera_global_transform) in query_camera.iter() {
+            hash_vec2(&mut scene_hasher, camera_global_transform.translation().truncate());
+        }
+        let scene_hash = scene_hasher.finish();
+
+        let (prev_scene_hash, stable_frames) = &mut *gi_scene_dirty_state;
+        if scene_hash == *prev_scene_hash {
+            *stable_frames = stable_frames.saturating_add(1);
+        } else {
+            *prev_scene_hash = scene_hash;
+            *stable_frames = 0;
+        }
+
+        gpu_dispatch_state.should_dispatch = match res_light_settings.compute_mode {
+            GiComputeMode::Continuous => true,
+            // Keep dispatching for `settle_frames` after the last change so the temporal probe
+            // jitter (keyed off `gpu_frame_counter`) has time to reconverge before we freeze.
+            GiComputeMode::Reactive { settle_frames } => *stable_frames < settle_frames,
+        };
+    }
+
     *gpu_frame_counter = (*gpu_frame_counter + 1) % (GI_SCREEN_PROBE_SIZE * GI_SCREEN_PROBE_SIZE);
 }
+
+/// Clears `data`, refills it via `fill`, then applies the soft-cap overflow check shared by every
+/// extracted GI entity kind: logs a warning once per distinct over-cap count (rather than every
+/// frame) and resets `warned` once the count drops back under `max_gi_entities`.
+fn extract_capped<T>(
+    data: &mut Vec<T>,
+    max_gi_entities: usize,
+    warned: &mut usize,
+    label: &str,
+    fill: impl FnOnce(&mut Vec<T>),
+) {
+    data.clear();
+    fill(data);
+
+    let len = data.len();
+    if len > max_gi_entities {
+        if len != *warned {
+            log::warn!(
+                "{} {} exceed max_gi_entities ({}); uploading all of them anyway, but consider \
+                 raising the soft cap",
+                len,
+                label,
+                max_gi_entities,
+            );
+            *warned = len;
+        }
+    } else {
+        *warned = 0;
+    }
+}
+
+fn hash_f32(hasher: &mut impl Hasher, value: f32) {
+    value.to_bits().hash(hasher);
+}
+
+fn hash_vec2(hasher: &mut impl Hasher, value: Vec2) {
+    hash_f32(hasher, value.x);
+    hash_f32(hasher, value.y);
+}
+
+fn hash_vec3(hasher: &mut impl Hasher, value: Vec3) {
+    hash_f32(hasher, value.x);
+    hash_f32(hasher, value.y);
+    hash_f32(hasher, value.z);
+}
+
+fn hash_color(hasher: &mut impl Hasher, value: Color) {
+    hash_f32(hasher, value.r());
+    hash_f32(hasher, value.g());
+    hash_f32(hasher, value.b());
+    hash_f32(hasher, value.a());
+}
@@ -0,0 +1,33 @@
+use bevy::prelude::*;
+
+#[derive(Default, Copy, Clone, Resource)]
+pub struct ComputedTargetSizes {
+    pub primary_target_size: Vec2,
+    pub primary_target_isize: IVec2,
+}
+
+/// Whether this frame's GI state gets uploaded to the GPU.
+///
+/// Written by [`crate::gi::pipeline_assets::system_extract_pipeline_assets`] based on
+/// [`crate::GiComputeMode`], and read back by [`should_dispatch_gi`], which gates only the buffer
+/// upload; this crate has no render-graph node or compute pass for the flag to skip.
+#[derive(Resource)]
+pub struct GiDispatchState {
+    pub should_dispatch: bool,
+}
+
+impl Default for GiDispatchState {
+    fn default() -> Self {
+        Self {
+            should_dispatch: true,
+        }
+    }
+}
+
+/// Run condition gating [`crate::gi::pipeline_assets::system_prepare_pipeline_assets`]: skips the
+/// GPU buffer upload entirely once [`GiDispatchState::should_dispatch`] goes false, so a settled
+/// `Reactive` scene keeps reusing the probe atlas already sitting on the GPU instead of
+/// re-uploading an identical one every frame.
+pub fn should_dispatch_gi(state: Res<GiDispatchState>) -> bool {
+    state.should_dispatch
+}
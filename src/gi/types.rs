@@ -0,0 +1,100 @@
+use bevy::prelude::*;
+
+/// Radial light source, emitting in all directions from a single point.
+#[derive(Component, Copy, Clone, Debug, Reflect)]
+pub struct OmniLightSource2D {
+    pub intensity: f32,
+    pub color: Color,
+    pub falloff: Vec3,
+    pub jitter_intensity: f32,
+    pub jitter_translation: f32,
+
+    /// Radius of the emitting disc, in world units. A non-zero radius turns the point light
+    /// into an area light so shadow edges can be softened: see [`shadow_softness`].
+    ///
+    /// [`shadow_softness`]: Self::shadow_softness
+    pub radius: f32,
+
+    /// Per-light multiplier on how much the penumbra widens with occluder distance, in `[0, 1]`.
+    /// `0.0` keeps shadows hard regardless of `radius`; `1.0` uses the full PCSS penumbra
+    /// estimated from the blocker search.
+    pub shadow_softness: f32,
+}
+
+impl Default for OmniLightSource2D {
+    fn default() -> Self {
+        Self {
+            intensity: 1.0,
+            color: Color::WHITE,
+            falloff: Vec3::new(1.5, 10.0, 0.005),
+            jitter_intensity: 0.0,
+            jitter_translation: 0.0,
+            radius: 0.0,
+            shadow_softness: 0.0,
+        }
+    }
+}
+
+/// Directional cone light, e.g. a flashlight, lamp cone, or car headlight.
+#[derive(Component, Copy, Clone, Debug, Reflect)]
+pub struct SpotLight2D {
+    pub intensity: f32,
+    pub color: Color,
+    pub falloff: Vec3,
+
+    /// Direction the cone points in, as an angle in radians (0 = +X, increasing counter-clockwise).
+    pub direction: f32,
+
+    /// Half-angle of the cone, in radians, where the attenuation reaches zero.
+    pub outer_angle: f32,
+
+    /// Half-angle of the cone, in radians, inside which attenuation is at full strength. Must be
+    /// `<= outer_angle`; the gap between the two gives the cone a smooth edge.
+    pub inner_angle: f32,
+
+    pub jitter_intensity: f32,
+    pub jitter_translation: f32,
+}
+
+impl Default for SpotLight2D {
+    fn default() -> Self {
+        Self {
+            intensity: 1.0,
+            color: Color::WHITE,
+            falloff: Vec3::new(1.5, 10.0, 0.005),
+            direction: 0.0,
+            outer_angle: std::f32::consts::FRAC_PI_4,
+            inner_angle: std::f32::consts::FRAC_PI_6,
+            jitter_intensity: 0.0,
+            jitter_translation: 0.0,
+        }
+    }
+}
+
+/// Global, directionless sky light contribution, akin to an ambient/skybox term.
+#[derive(Component, Copy, Clone, Debug, Reflect)]
+pub struct SkylightLight2D {
+    pub color: Color,
+    pub intensity: f32,
+}
+
+impl Default for SkylightLight2D {
+    fn default() -> Self {
+        Self {
+            color: Color::WHITE,
+            intensity: 0.0,
+        }
+    }
+}
+
+/// Axis-aligned occluder box that casts shadows against [`OmniLightSource2D`]s.
+#[derive(Component, Copy, Clone, Debug, Default, Reflect)]
+pub struct LightOccluder2D {
+    pub h_size: Vec2,
+}
+
+/// Axis-aligned mask that punches a hole in the skylight contribution (e.g. a roof).
+#[derive(Component, Copy, Clone, Debug, Default, Reflect)]
+pub struct SkylightMask2D {
+    pub h_size: Vec2,
+}
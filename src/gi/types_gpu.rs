@@ -0,0 +1,179 @@
+use bevy::prelude::*;
+use bevy::render::render_resource::ShaderType;
+
+use crate::gi::types::{OmniLightSource2D, SpotLight2D};
+
+#[derive(Copy, Clone, ShaderType)]
+pub struct GpuCameraParams {
+    pub view_proj: Mat4,
+    pub inverse_view_proj: Mat4,
+    pub screen_size: Vec2,
+    pub screen_size_inv: Vec2,
+    pub sdf_scale: Vec2,
+    pub inv_sdf_scale: Vec2,
+}
+
+impl Default for GpuCameraParams {
+    fn default() -> Self {
+        Self {
+            view_proj: Mat4::IDENTITY,
+            inverse_view_proj: Mat4::IDENTITY,
+            screen_size: Vec2::ZERO,
+            screen_size_inv: Vec2::ZERO,
+            sdf_scale: Vec2::ONE,
+            inv_sdf_scale: Vec2::ONE,
+        }
+    }
+}
+
+/// One [`GpuCameraParams`] per GI camera (any entity with the `FloorCamera` marker), indexed by
+/// the same camera index used for [`GpuProbeArray`]'s per-camera probe range.
+#[derive(Clone, Default, ShaderType)]
+pub struct GpuCameraParamsArray {
+    pub count: i32,
+    #[size(runtime)]
+    pub data: Vec<GpuCameraParams>,
+}
+
+#[derive(Copy, Clone, Default, ShaderType)]
+pub struct GpuLightPassParams {
+    pub frame_counter: i32,
+    pub probe_size: i32,
+    pub probe_atlas_cols: i32,
+    pub probe_atlas_rows: i32,
+    pub reservoir_size: i32,
+    pub smooth_kernel_size_h: i32,
+    pub smooth_kernel_size_w: i32,
+    pub direct_light_contrib: f32,
+    pub indirect_light_contrib: f32,
+    pub indirect_rays_radius_factor: f32,
+    pub indirect_rays_per_sample: i32,
+    pub skylight_color: Vec3,
+
+    /// Number of Poisson-disc shadow-ray samples taken per pixel when an [`OmniLightSource2D`]
+    /// has a non-zero `radius`. Traded directly against direct-light shader cost.
+    pub shadow_samples: i32,
+
+    /// Global multiplier applied on top of each light's own `shadow_softness`.
+    pub shadow_softness_multiplier: f32,
+}
+
+#[derive(Copy, Clone, Default, ShaderType)]
+pub struct GpuProbeData {
+    pub camera_pose: Vec2,
+}
+
+/// Holds `GI_SCREEN_PROBE_SIZE * GI_SCREEN_PROBE_SIZE` temporal-accumulation probe slots *per
+/// camera*, laid out back to back (camera index major, frame-counter minor) so each GI camera
+/// gets its own rotating probe atlas instead of sharing one global set of probes.
+#[derive(Clone, Default, ShaderType)]
+pub struct GpuProbeArray {
+    #[size(runtime)]
+    pub data: Vec<GpuProbeData>,
+}
+
+#[derive(Copy, Clone, Default, ShaderType)]
+pub struct GpuOmniLightSource {
+    pub center: Vec2,
+    pub intensity: f32,
+    pub color: Vec3,
+    pub falloff: Vec3,
+    pub radius: f32,
+    pub shadow_softness: f32,
+}
+
+impl GpuOmniLightSource {
+    pub fn new(light: OmniLightSource2D, center: Vec2) -> Self {
+        Self {
+            center,
+            intensity: light.intensity,
+            color: Vec3::new(light.color.r(), light.color.g(), light.color.b()),
+            falloff: light.falloff,
+            radius: light.radius,
+            shadow_softness: light.shadow_softness,
+        }
+    }
+}
+
+/// Backed by a read-only storage buffer rather than a uniform, so `data` can grow past any
+/// compile-time cap: see [`crate::LightPassParams::max_gi_entities`] for the soft limit that
+/// still applies, now enforced with a warning instead of a silent truncation.
+#[derive(Clone, Default, ShaderType)]
+pub struct GpuOmniLightSourceArray {
+    pub count: i32,
+    #[size(runtime)]
+    pub data: Vec<GpuOmniLightSource>,
+}
+
+#[derive(Copy, Clone, Default, ShaderType)]
+pub struct GpuSpotLightSource {
+    pub center: Vec2,
+    pub intensity: f32,
+    pub color: Vec3,
+    pub falloff: Vec3,
+    pub direction: f32,
+    pub outer_angle_cos: f32,
+    pub inner_angle_cos: f32,
+}
+
+impl GpuSpotLightSource {
+    pub fn new(light: SpotLight2D, center: Vec2) -> Self {
+        Self {
+            center,
+            intensity: light.intensity,
+            color: Vec3::new(light.color.r(), light.color.g(), light.color.b()),
+            falloff: light.falloff,
+            direction: light.direction,
+            outer_angle_cos: light.outer_angle.cos(),
+            inner_angle_cos: light.inner_angle.cos(),
+        }
+    }
+}
+
+#[derive(Clone, Default, ShaderType)]
+pub struct GpuSpotLightSourceArray {
+    pub count: i32,
+    #[size(runtime)]
+    pub data: Vec<GpuSpotLightSource>,
+}
+
+#[derive(Copy, Clone, Default, ShaderType)]
+pub struct GpuLightOccluder2D {
+    pub center: Vec2,
+    pub h_size: Vec2,
+}
+
+impl GpuLightOccluder2D {
+    pub fn new(transform: &Transform, h_size: Vec2) -> Self {
+        Self {
+            center: transform.translation.truncate(),
+            h_size,
+        }
+    }
+}
+
+#[derive(Clone, Default, ShaderType)]
+pub struct GpuLightOccluder2DArray {
+    pub count: i32,
+    #[size(runtime)]
+    pub data: Vec<GpuLightOccluder2D>,
+}
+
+#[derive(Copy, Clone, Default, ShaderType)]
+pub struct GpuSkylightMaskData {
+    pub center: Vec2,
+    pub h_size: Vec2,
+}
+
+impl GpuSkylightMaskData {
+    pub fn new(center: Vec2, h_size: Vec2) -> Self {
+        Self { center, h_size }
+    }
+}
+
+#[derive(Clone, Default, ShaderType)]
+pub struct GpuSkylightMaskArray {
+    pub count: i32,
+    #[size(runtime)]
+    pub data: Vec<GpuSkylightMaskData>,
+}
@@ -0,0 +1,160 @@
+use bevy::prelude::*;
+use bevy::render::{ExtractSchedule, RenderApp, RenderSet};
+
+pub mod gi;
+
+pub mod prelude {
+    pub use crate::gi::animation::{AnimatedLight2D, EasingCurve, Keyframe, PlaybackMode, Track};
+    pub use crate::gi::types::{
+        LightOccluder2D, OmniLightSource2D, SkylightLight2D, SkylightMask2D, SpotLight2D,
+    };
+    pub use crate::{
+        setup_post_processing_camera, BevyMagicLight2DPlugin, BevyMagicLight2DSettings,
+        FloorCamera, GiComputeMode, LightPassParams, ObjectsCamera, PostProcessingTarget,
+        SpriteCamera, WallsCamera, CAMERA_LAYER_FLOOR, CAMERA_LAYER_OBJECTS, CAMERA_LAYER_WALLS,
+    };
+}
+
+pub const CAMERA_LAYER_FLOOR: usize = 1;
+pub const CAMERA_LAYER_WALLS: usize = 2;
+pub const CAMERA_LAYER_OBJECTS: usize = 3;
+
+/// Marker for the camera rendering the floor layer, also used as the GI reference camera.
+#[derive(Component, Default)]
+pub struct FloorCamera;
+
+/// Marker for the camera rendering the walls layer.
+#[derive(Component, Default)]
+pub struct WallsCamera;
+
+/// Marker for the camera rendering the dynamic objects layer.
+#[derive(Component, Default)]
+pub struct ObjectsCamera;
+
+/// Marker for any camera that composites one of the sprite layers (floor/walls/objects), used to
+/// move all of a viewport's layer cameras together. GI itself keys off [`FloorCamera`] alone, one
+/// per viewport, since the walls/objects cameras share that same viewpoint.
+#[derive(Component, Default)]
+pub struct SpriteCamera;
+
+/// Render targets the post-processing pipeline composites the floor/walls/objects layers into.
+#[derive(Resource, Default, Clone)]
+pub struct PostProcessingTarget {
+    pub handles: Option<(Handle<Image>, Handle<Image>, Handle<Image>)>,
+}
+
+#[derive(Copy, Clone)]
+pub struct LightPassParams {
+    pub reservoir_size: i32,
+    pub smooth_kernel_size: (i32, i32),
+    pub direct_light_contrib: f32,
+    pub indirect_light_contrib: f32,
+    pub indirect_rays_per_sample: i32,
+    pub indirect_rays_radius_factor: f32,
+
+    /// Poisson-disc shadow-ray sample count for soft shadows; `0` keeps the legacy single-ray
+    /// hard-shadow behavior regardless of a light's `radius`.
+    pub shadow_samples: i32,
+
+    /// Global multiplier on the PCSS penumbra widening, in `[0, 1]`.
+    pub shadow_softness_multiplier: f32,
+
+    /// Soft cap on how many lights/occluders/skylight masks get uploaded to the GPU per frame.
+    /// The backing storage buffers grow on demand past this, so it's not a hard ceiling like it
+    /// used to be; exceeding it just logs a warning instead of silently dropping entities.
+    pub max_gi_entities: usize,
+}
+
+impl Default for LightPassParams {
+    fn default() -> Self {
+        Self {
+            reservoir_size: 16,
+            smooth_kernel_size: (3, 3),
+            direct_light_contrib: 0.5,
+            indirect_light_contrib: 0.5,
+            indirect_rays_per_sample: 16,
+            indirect_rays_radius_factor: 3.0,
+            shadow_samples: 0,
+            shadow_softness_multiplier: 0.0,
+            max_gi_entities: crate::gi::constants::DEFAULT_MAX_GI_ENTITIES,
+        }
+    }
+}
+
+/// Controls when the GI compute passes actually run.
+#[derive(Copy, Clone, Default)]
+pub enum GiComputeMode {
+    /// Re-run the light/GI passes every frame, regardless of whether the scene changed.
+    #[default]
+    Continuous,
+
+    /// Skip the GPU upload of the extracted GI buffers once the tracked scene state (lights,
+    /// occluders, skylight, camera pose) has been unchanged for `settle_frames` consecutive
+    /// frames, leaving the last uploaded buffers in place. No light/GI compute pass is dispatched
+    /// by this crate, so the upload is the only work skipped. Intended for mostly-static scenes
+    /// such as paused games or menus.
+    Reactive { settle_frames: u32 },
+}
+
+#[derive(Resource, Copy, Clone, Default)]
+pub struct BevyMagicLight2DSettings {
+    pub light_pass_params: LightPassParams,
+    pub compute_mode: GiComputeMode,
+}
+
+pub struct BevyMagicLight2DPlugin;
+
+impl Plugin for BevyMagicLight2DPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<BevyMagicLight2DSettings>()
+            .init_resource::<PostProcessingTarget>()
+            .init_resource::<gi::resource::ComputedTargetSizes>()
+            .add_system(gi::animation::system_advance_light_animations);
+
+        let render_app = app.sub_app_mut(RenderApp);
+        render_app
+            .init_resource::<gi::pipeline_assets::LightPassPipelineAssets>()
+            .init_resource::<gi::resource::GiDispatchState>()
+            .add_system(
+                gi::pipeline_assets::system_extract_pipeline_assets.in_schedule(ExtractSchedule),
+            )
+            .add_system(
+                gi::pipeline_assets::system_prepare_pipeline_assets
+                    .in_set(RenderSet::Prepare)
+                    .run_if(gi::resource::should_dispatch_gi),
+            );
+    }
+}
+
+pub fn setup_post_processing_camera(
+    mut images: ResMut<Assets<Image>>,
+    mut post_processing_target: ResMut<PostProcessingTarget>,
+) {
+    let size = bevy::render::render_resource::Extent3d {
+        width: 512,
+        height: 512,
+        depth_or_array_layers: 1,
+    };
+
+    let mut make_target = || {
+        let mut image = Image {
+            texture_descriptor: bevy::render::render_resource::TextureDescriptor {
+                label: None,
+                size,
+                dimension: bevy::render::render_resource::TextureDimension::D2,
+                format: bevy::render::render_resource::TextureFormat::Rgba8UnormSrgb,
+                mip_level_count: 1,
+                sample_count: 1,
+                usage: bevy::render::render_resource::TextureUsages::TEXTURE_BINDING
+                    | bevy::render::render_resource::TextureUsages::COPY_DST
+                    | bevy::render::render_resource::TextureUsages::RENDER_ATTACHMENT,
+                view_formats: &[],
+            },
+            ..default()
+        };
+        image.resize(size);
+        images.add(image)
+    };
+
+    post_processing_target.handles = Some((make_target(), make_target(), make_target()));
+}